@@ -1,42 +1,100 @@
-//! The `qwant` module handles the scraping of results from the qwant search engine
-//! by querying the upstream qwant search engine with user provided query and with a page
-//! number if provided.
+//! The `qwant` module handles querying results from the qwant search engine's
+//! public JSON API with a user provided query and an optional page number.
 
 use reqwest::header::HeaderMap;
 use reqwest::Client;
-use scraper::Html;
+use serde::Deserialize;
 use std::collections::HashMap;
 
-use super::search_result_parser::SearchResultParser;
+use super::request_throttle::ThrottleConfig;
+use super::retry::{retry_with_backoff_with_config, RetryConfig};
 use crate::models::aggregation_models::SearchResult;
-use crate::models::engine_models::{EngineError, SearchEngine};
+use crate::models::engine_models::{normalize_locale, EngineError, SearchEngine, DEFAULT_LOCALE};
 use error_stack::{Report, Result, ResultExt};
 
 /// A new Qwant engine type defined in-order to implement the `SearchEngine` trait which allows to
 /// reduce code duplication as well as allows to create vector of different search engines easily.
-
 pub struct Qwant {
-    /// The parser used to extract search results from HTML documents.
-    parser: SearchResultParser,
+    /// The jitter window and referer/cookie pool used for upstream requests.
+    throttle_config: ThrottleConfig,
+    /// The attempt count and backoff base used when retrying transient upstream failures.
+    retry_config: RetryConfig,
+}
+
+/// The top-level shape of a response returned by Qwant's `v3/search/web` endpoint.
+#[derive(Deserialize)]
+struct QwantResponse {
+    /// The payload containing the actual search results.
+    data: QwantData,
+}
+
+/// The `data` field of a Qwant API response.
+#[derive(Deserialize)]
+struct QwantData {
+    /// The wrapper around the list of result items.
+    result: QwantResultList,
+}
+
+/// The `data.result` field of a Qwant API response.
+#[derive(Deserialize)]
+struct QwantResultList {
+    /// The individual search result entries.
+    items: Vec<QwantResultItem>,
+}
+
+/// A single search result entry as returned by the Qwant API.
+#[derive(Deserialize)]
+struct QwantResultItem {
+    /// The title of the search result.
+    title: String,
+    /// The url that the search result points to.
+    url: String,
+    /// A short description of the search result.
+    desc: String,
 }
 
 impl Qwant {
-    /// Creates a new instance of Qwant with a default configuration.
+    /// Creates a new instance of Qwant with the default throttle and retry configuration.
     ///
     /// # Returns
     ///
     /// Returns a `Result` containing `Qwant` if successful, otherwise an `EngineError`.
     pub fn new() -> Result<Self, EngineError> {
+        Self::with_config(ThrottleConfig::default(), RetryConfig::default())
+    }
+
+    /// Creates a new instance of Qwant with a caller-supplied throttle and retry
+    /// configuration, for deployments that need a different jitter window, referer/cookie
+    /// pool, retry attempt count, or backoff base than the defaults.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing `Qwant` if successful, otherwise an `EngineError`.
+    pub fn with_config(
+        throttle_config: ThrottleConfig,
+        retry_config: RetryConfig,
+    ) -> Result<Self, EngineError> {
         Ok(Self {
-            parser: SearchResultParser::new(
-                "._2NDle nt3hI",
-                "._2NDle nt3hI",
-                "._35zId _3A7p7 RMB_d eoseI>a",
-                "._35zId _3A7p7 RMB_d eoseI>a",
-                "._2-LMx XqdKF _1UMq0 _29nLp _3PXjk>span",
-            )?,
+            throttle_config,
+            retry_config,
         })
     }
+
+    /// Maps the decoded result items from a `QwantResponse` into the `HashMap` shape
+    /// returned by [`SearchEngine::results`], keyed by each result's url. Factored out of
+    /// `results_with_locale` so tests can exercise the exact mapping used in production
+    /// instead of carrying an independent copy of it.
+    fn map_items_to_results(items: Vec<QwantResultItem>) -> HashMap<String, SearchResult> {
+        items
+            .into_iter()
+            .map(|item| {
+                (
+                    item.url.clone(),
+                    SearchResult::new(&item.title, &item.url, &item.desc, &["qwant"]),
+                )
+            })
+            .collect()
+    }
 }
 
 #[async_trait::async_trait]
@@ -49,7 +107,9 @@ impl SearchEngine for Qwant {
     /// * `page` - The page number for pagination.
     /// * `user_agent` - The user agent string.
     /// * `client` - The reqwest client for making HTTP requests.
-    /// * `_safe_search` - A parameter for safe search (not currently used).
+    /// * `safe_search` - The safe search level requested by the user. This is mapped onto
+    ///   Qwant's own `safesearch` query parameter, where `0` is off, `1` is moderate and `2`
+    ///   is strict. Values greater than `2` are clamped to the strictest mode.
     ///
     /// # Returns
     ///
@@ -61,51 +121,129 @@ impl SearchEngine for Qwant {
         page: u32,
         user_agent: &str,
         client: &Client,
-        _safe_search: u8,
+        safe_search: u8,
     ) -> Result<HashMap<String, SearchResult>, EngineError> {
-        // Page number can be missing or empty string and so appropriate handling is required
-        // so that upstream server recieves valid page number.
-        let url: String = match page {
-            1 | 0 => {
-                format!("https://www.qwant.com/?q={query}&s=1")
-            }
-            _ => {
-                format!("https://www.qwant.com/?q={query}&s={page}",)
-            }
+        self.results_with_locale(query, page, user_agent, client, safe_search, DEFAULT_LOCALE)
+            .await
+    }
+
+    /// Retrieves search results from Qwant, additionally honoring a locale/region hint.
+    ///
+    /// # Arguments
+    ///
+    /// * `locale` - The locale/region to request results in (e.g. `en_US`, `fr_FR`). Falls
+    ///   back to [`DEFAULT_LOCALE`] if unrecognized.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing a `HashMap` of search results if successful, otherwise an `EngineError`.
+    async fn results_with_locale(
+        &self,
+        query: &str,
+        page: u32,
+        user_agent: &str,
+        client: &Client,
+        safe_search: u8,
+        locale: &str,
+    ) -> Result<HashMap<String, SearchResult>, EngineError> {
+        // Qwant only understands three safe search levels, so anything stricter than
+        // `2` gets clamped down to it rather than being rejected by the upstream server.
+        let safe_search: u8 = std::cmp::min(safe_search, 2);
+
+        // Reject locales Qwant isn't known to accept before they reach the upstream
+        // server, falling back to the crate-wide default rather than a bare 400.
+        let locale = normalize_locale(locale);
+
+        // Qwant paginates its JSON API with a zero-based item offset rather than a page
+        // number, so a missing or empty page is treated as page one.
+        let offset = match page {
+            1 | 0 => 0,
+            _ => (page - 1) * 10,
         };
 
-        // initializing HeaderMap and adding appropriate headers.
+        let url: String = format!(
+            "https://api.qwant.com/v3/search/web?q={query}&count=10&offset={offset}&locale={locale}&safesearch={safe_search}"
+        );
+
+        // initializing HeaderMap and adding appropriate headers. The `Referer`/`Cookie`
+        // headers are deliberately left unset here: `fetch_html_from_upstream` rotates
+        // them from a shared pool to avoid presenting a single, easily blocked identity.
         let header_map = HeaderMap::try_from(&HashMap::from([
             ("USER_AGENT".to_string(), user_agent.to_string()),
-            ("REFERER".to_string(), "https://google.com/".to_string()),
             (
                 "CONTENT_TYPE".to_string(),
                 "application/x-www-form-urlencoded".to_string(),
             ),
-            (
-                "COOKIE".to_string(),
-                "ab_test_group=1; home=daily".to_string(),
-            ),
         ]))
         .change_context(EngineError::UnexpectedError)?;
 
-        let document: Html = Html::parse_document(
-            &Qwant::fetch_html_from_upstream(self, &url, header_map, client).await?,
-        );
+        // Transient upstream failures (connection resets, timeouts, HTTP 5xx) are retried
+        // with exponential backoff rather than failing the whole request outright, and the
+        // jitter/referer pool used for each attempt comes from this instance's own config.
+        let response = retry_with_backoff_with_config(&self.retry_config, || {
+            Qwant::fetch_html_from_upstream_with_config(
+                self,
+                &url,
+                header_map.clone(),
+                client,
+                &self.throttle_config,
+            )
+        })
+        .await?;
 
-        if self.parser.parse_for_no_results(&document).next().is_some() {
+        let response: QwantResponse = serde_json::from_str(&response)
+            .change_context(EngineError::UnexpectedError)?;
+
+        if response.data.result.items.is_empty() {
             return Err(Report::new(EngineError::EmptyResultSet));
         }
 
-        // scrape all the results from the html
-        self.parser
-            .parse_for_results(&document, |title, url, desc| {
-                Some(SearchResult::new(
-                    title.inner_html().trim(),
-                    url.inner_html().trim(),
-                    desc.inner_html().trim(),
-                    &["qwant"],
-                ))
-            })
+        Ok(Self::map_items_to_results(response.data.result.items))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_qwant_response_into_search_results() {
+        let body = r#"{
+            "data": {
+                "result": {
+                    "items": [
+                        {
+                            "title": "Rust Programming Language",
+                            "url": "https://www.rust-lang.org/",
+                            "desc": "A language empowering everyone to build reliable software."
+                        },
+                        {
+                            "title": "Rust (programming language) - Wikipedia",
+                            "url": "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+                            "desc": "Rust is a multi-paradigm, general-purpose programming language."
+                        }
+                    ]
+                }
+            }
+        }"#;
+
+        let response: QwantResponse = serde_json::from_str(body).expect("valid QwantResponse JSON");
+        let results = Qwant::map_items_to_results(response.data.result.items);
+
+        assert_eq!(results.len(), 2);
+        assert!(results.contains_key("https://www.rust-lang.org/"));
+        assert_eq!(
+            results["https://www.rust-lang.org/"].title,
+            "Rust Programming Language"
+        );
+    }
+
+    #[test]
+    fn empty_items_deserializes_without_error() {
+        let body = r#"{"data": {"result": {"items": []}}}"#;
+
+        let response: QwantResponse = serde_json::from_str(body).expect("valid QwantResponse JSON");
+
+        assert!(response.data.result.items.is_empty());
     }
 }
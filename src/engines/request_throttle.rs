@@ -0,0 +1,83 @@
+//! Shared anti-blocking helpers used before issuing a request to an upstream search
+//! engine: a small randomized delay and a rotating pool of `Referer`/`Cookie` header
+//! pairs, so a burst of requests doesn't present a single, easily fingerprinted and
+//! rate-limited identity to the upstream server.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::time::Duration;
+
+/// A `Referer`/`Cookie` pair rotated across upstream requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefererProfile {
+    /// The value to send as the `Referer` header.
+    pub referer: String,
+    /// The value to send as the `Cookie` header.
+    pub cookie: String,
+}
+
+impl RefererProfile {
+    /// Creates a new referer/cookie profile.
+    pub fn new(referer: impl Into<String>, cookie: impl Into<String>) -> Self {
+        Self {
+            referer: referer.into(),
+            cookie: cookie.into(),
+        }
+    }
+}
+
+/// Runtime-configurable parameters for the anti-blocking layer, so the jitter window and
+/// the rotation pool aren't baked into the binary and can be tuned per deployment.
+#[derive(Debug, Clone)]
+pub struct ThrottleConfig {
+    /// Upper bound, in milliseconds, of the randomized delay inserted before an upstream request.
+    pub max_jitter_ms: u64,
+    /// The pool of referer/cookie profiles rotated between upstream requests.
+    pub referer_pool: Vec<RefererProfile>,
+}
+
+impl Default for ThrottleConfig {
+    /// The default jitter window and referer/cookie pool used when no configuration is
+    /// supplied.
+    fn default() -> Self {
+        Self {
+            max_jitter_ms: 250,
+            referer_pool: vec![
+                RefererProfile::new("https://google.com/", "ab_test_group=1; home=daily"),
+                RefererProfile::new("https://duckduckgo.com/", "ab_test_group=2; home=daily"),
+                RefererProfile::new("https://www.bing.com/", "ab_test_group=3; home=daily"),
+            ],
+        }
+    }
+}
+
+/// Sleeps for a small, uniformly random duration in the `0..=config.max_jitter_ms` window
+/// before an upstream request is issued, so requests fired in quick succession don't land
+/// on the upstream server at a suspiciously regular cadence.
+pub async fn jittered_delay(config: &ThrottleConfig) {
+    let millis = rand::thread_rng().gen_range(0..=config.max_jitter_ms);
+    tokio::time::sleep(Duration::from_millis(millis)).await;
+}
+
+/// Picks a random `Referer`/`Cookie` profile from `config`'s rotation pool.
+///
+/// # Panics
+///
+/// Panics if `config.referer_pool` is empty; a `ThrottleConfig` should always be
+/// constructed with at least one profile.
+pub fn random_referer_profile(config: &ThrottleConfig) -> RefererProfile {
+    config
+        .referer_pool
+        .choose(&mut rand::thread_rng())
+        .expect("ThrottleConfig::referer_pool must not be empty")
+        .clone()
+}
+
+/// Returns `true` if the given response status or body looks like a block/captcha page
+/// rather than a genuine result page, so the caller can surface that distinction instead
+/// of treating it as an ordinary empty result set.
+pub fn looks_blocked(status: reqwest::StatusCode, body: &str) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        || body.contains("captcha")
+        || body.contains("Are you a robot")
+}
@@ -0,0 +1,88 @@
+//! Retry helper shared by engines to retry transient upstream failures (connection
+//! resets, timeouts, HTTP 5xx) with exponential backoff and jitter, recording the full
+//! attempt history as `error-stack` context frames.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use error_stack::{Result, ResultExt};
+use rand::Rng;
+
+use crate::models::engine_models::EngineError;
+
+/// Runtime-configurable parameters for [`retry_with_backoff_with_config`], so the attempt
+/// count and backoff base aren't baked into the binary and can be tuned per deployment.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    /// Maximum number of attempts made before giving up and returning the last error.
+    pub max_attempts: u32,
+    /// Base delay, in milliseconds, used to compute the exponential backoff between attempts.
+    pub base_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    /// The default attempt count and backoff base used when no configuration is supplied.
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_backoff_ms: 100,
+        }
+    }
+}
+
+/// Retries `attempt` with the default [`RetryConfig`]. See
+/// [`retry_with_backoff_with_config`] for the configurable version and the full
+/// behavior description.
+pub async fn retry_with_backoff<F, Fut, T>(attempt: F) -> Result<T, EngineError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, EngineError>>,
+{
+    retry_with_backoff_with_config(&RetryConfig::default(), attempt).await
+}
+
+/// Retries `attempt` up to `config.max_attempts` times on transient
+/// `EngineError::RequestError` failures, waiting an exponentially increasing, jittered
+/// delay (based on `config.base_backoff_ms`) between attempts. Every failed attempt is
+/// attached to the error as an `error-stack` context frame recording the attempt number
+/// and elapsed time, so a `Report<EngineError>` returned after retries are exhausted
+/// carries the full retry history instead of collapsing to a single opaque error.
+pub async fn retry_with_backoff_with_config<F, Fut, T>(
+    config: &RetryConfig,
+    mut attempt: F,
+) -> Result<T, EngineError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, EngineError>>,
+{
+    let max_attempts = config.max_attempts.max(1);
+    let mut history: Vec<String> = Vec::new();
+
+    for attempt_number in 1..=max_attempts {
+        let started = Instant::now();
+
+        match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(err) => {
+                let transient = matches!(err.current_context(), EngineError::RequestError);
+                history.push(format!(
+                    "attempt {attempt_number}/{max_attempts} failed after {:?}: {}",
+                    started.elapsed(),
+                    err.current_context()
+                ));
+
+                if !transient || attempt_number == max_attempts {
+                    return Err(history
+                        .drain(..)
+                        .fold(err, |err, frame| err.attach_printable(frame)));
+                }
+
+                let backoff_ms = config.base_backoff_ms * 2u64.pow(attempt_number - 1);
+                let jitter_ms = rand::thread_rng().gen_range(0..config.base_backoff_ms.max(1));
+                tokio::time::sleep(Duration::from_millis(backoff_ms + jitter_ms)).await;
+            }
+        }
+    }
+
+    unreachable!("the loop above always returns by the final attempt")
+}
@@ -0,0 +1,209 @@
+//! This module provides the error type and the `SearchEngine` trait shared by every
+//! upstream search engine implementation in this crate.
+
+use std::collections::HashMap;
+
+use error_stack::{Report, Result, ResultExt};
+use reqwest::header::{HeaderMap, COOKIE, REFERER};
+use reqwest::Client;
+use thiserror::Error;
+
+use crate::engines::request_throttle::{
+    jittered_delay, looks_blocked, random_referer_profile, ThrottleConfig,
+};
+use crate::models::aggregation_models::SearchResult;
+
+/// The locale used when a caller doesn't supply one, or supplies one this crate doesn't
+/// recognize.
+pub const DEFAULT_LOCALE: &str = "en_US";
+
+/// The set of locales known to be accepted by the upstream search engines this crate
+/// queries. Kept deliberately small; extend it as more upstreams are verified to accept
+/// a given locale.
+const KNOWN_LOCALES: &[&str] = &[
+    "en_US", "en_GB", "fr_FR", "de_DE", "es_ES", "it_IT", "pt_BR", "nl_NL",
+];
+
+/// Validates `locale` against [`KNOWN_LOCALES`], falling back to [`DEFAULT_LOCALE`] for
+/// anything unrecognized so that callers never forward a locale likely to be rejected by
+/// an upstream search engine with a 400.
+pub fn normalize_locale(locale: &str) -> &str {
+    KNOWN_LOCALES
+        .iter()
+        .find(|&&known| known == locale)
+        .copied()
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// A type to represent the different errors that can occur while requesting results
+/// from an upstream search engine.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum EngineError {
+    /// Denotes that the upstream search engine is unable to handle the request.
+    #[error("the upstream search engine is currently unable to handle the request")]
+    RequestError,
+
+    /// Denotes that the above mentioned engine has returned no results for the given query.
+    #[error("the upstream search engine has returned an empty result set")]
+    EmptyResultSet,
+
+    /// Denotes that the upstream search engine blocked or rate-limited the request (for
+    /// example via an HTTP 429 or a captcha/interstitial page), as opposed to the engine
+    /// genuinely having no results for the query.
+    #[error("the upstream search engine blocked or rate-limited the request")]
+    BlockedByUpstream,
+
+    /// Denotes an unexpected error occurred while processing the request/response.
+    #[error("an unexpected error occurred while processing the request/response")]
+    UnexpectedError,
+}
+
+/// A trait to define the common behaviour of all the different upstream search engines
+/// that this crate aggregates results from.
+#[async_trait::async_trait]
+pub trait SearchEngine: Sync + Send {
+    /// Fetches the raw response body for `url` from an upstream search engine, applying
+    /// the shared anti-blocking measures (jittered delay, rotated `Referer`/`Cookie`
+    /// headers and block/captcha detection) common to every engine, so individual
+    /// engines don't each need to reimplement them.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to issue the request to.
+    /// * `header_map` - The headers to send with the request, besides `Referer` and
+    ///   `Cookie`, which are overwritten with a rotated profile from the shared pool.
+    /// * `client` - The reqwest client for making HTTP requests.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the response body if successful, otherwise an `EngineError`.
+    async fn fetch_html_from_upstream(
+        &self,
+        url: &str,
+        header_map: HeaderMap,
+        client: &Client,
+    ) -> Result<String, EngineError> {
+        self.fetch_html_from_upstream_with_config(url, header_map, client, &ThrottleConfig::default())
+            .await
+    }
+
+    /// Same as [`SearchEngine::fetch_html_from_upstream`], but with the jitter window and
+    /// referer/cookie rotation pool taken from `throttle_config` instead of the defaults,
+    /// so a caller that needs different anti-blocking behavior (e.g. a tighter jitter
+    /// budget, or a deployment-specific referer pool) isn't stuck with hardcoded values.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to issue the request to.
+    /// * `header_map` - The headers to send with the request, besides `Referer` and
+    ///   `Cookie`, which are overwritten with a rotated profile from `throttle_config`.
+    /// * `client` - The reqwest client for making HTTP requests.
+    /// * `throttle_config` - The jitter window and referer/cookie pool to use.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the response body if successful, otherwise an `EngineError`.
+    async fn fetch_html_from_upstream_with_config(
+        &self,
+        url: &str,
+        mut header_map: HeaderMap,
+        client: &Client,
+        throttle_config: &ThrottleConfig,
+    ) -> Result<String, EngineError> {
+        // Jitter the request timing and rotate the referer/cookie identity presented to
+        // the upstream server, so a burst of requests isn't trivially fingerprinted.
+        jittered_delay(throttle_config).await;
+
+        let profile = random_referer_profile(throttle_config);
+        header_map.insert(
+            REFERER,
+            profile
+                .referer
+                .parse()
+                .change_context(EngineError::UnexpectedError)?,
+        );
+        header_map.insert(
+            COOKIE,
+            profile
+                .cookie
+                .parse()
+                .change_context(EngineError::UnexpectedError)?,
+        );
+
+        let response = client
+            .get(url)
+            .headers(header_map)
+            .send()
+            .await
+            .change_context(EngineError::RequestError)?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .change_context(EngineError::RequestError)?;
+
+        if looks_blocked(status, &body) {
+            return Err(Report::new(EngineError::BlockedByUpstream));
+        }
+
+        // Treat upstream server errors as a transient `RequestError` so that callers
+        // wrapping this call in `retry::retry_with_backoff` retry them automatically.
+        if status.is_server_error() {
+            return Err(Report::new(EngineError::RequestError)
+                .attach_printable(format!("upstream responded with status {status}")));
+        }
+
+        Ok(body)
+    }
+
+    /// Retrieves the search results for the given query from the upstream search engine.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The search query.
+    /// * `page` - The page number for pagination.
+    /// * `user_agent` - The user agent string.
+    /// * `client` - The reqwest client for making HTTP requests.
+    /// * `safe_search` - The safe search level requested by the user.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing a `HashMap` of search results if successful, otherwise an `EngineError`.
+    async fn results(
+        &self,
+        query: &str,
+        page: u32,
+        user_agent: &str,
+        client: &Client,
+        safe_search: u8,
+    ) -> Result<HashMap<String, SearchResult>, EngineError>;
+
+    /// Retrieves the search results for the given query, honoring a locale/region hint.
+    ///
+    /// Provided so that adding locale support doesn't break every existing implementor of
+    /// this trait: the default just forwards to [`SearchEngine::results`] and ignores
+    /// `locale` entirely. Engines that understand locale (currently only `Qwant`) override
+    /// this method instead of changing the required `results` signature.
+    ///
+    /// # Arguments
+    ///
+    /// * `locale` - The locale/region (e.g. `en_US`, `fr_FR`) to request results in.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing a `HashMap` of search results if successful, otherwise an `EngineError`.
+    async fn results_with_locale(
+        &self,
+        query: &str,
+        page: u32,
+        user_agent: &str,
+        client: &Client,
+        safe_search: u8,
+        locale: &str,
+    ) -> Result<HashMap<String, SearchResult>, EngineError> {
+        let _ = locale;
+        self.results(query, page, user_agent, client, safe_search)
+            .await
+    }
+}